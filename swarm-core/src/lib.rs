@@ -16,6 +16,161 @@ pub enum ParticleType {
     Drifter, // Slow, low attraction, large
 }
 
+impl ParticleType {
+    /// Per-type multipliers applied to the global flock weights, so each
+    /// personality keeps its existing flavor under boids steering.
+    fn flock_multipliers(self) -> (f32, f32, f32) {
+        // (separation, alignment, cohesion)
+        match self {
+            ParticleType::Scout => (1.0, 1.4, 0.9),
+            ParticleType::Anchor => (1.0, 1.0, 1.0),
+            ParticleType::Drifter => (1.0, 0.8, 0.6),
+        }
+    }
+
+    /// Row/column index into the species relation matrix
+    fn index(self) -> usize {
+        match self {
+            ParticleType::Scout => 0,
+            ParticleType::Anchor => 1,
+            ParticleType::Drifter => 2,
+        }
+    }
+}
+
+/// Number of distinct `ParticleType` variants, sizing the relation matrix
+const PARTICLE_TYPE_COUNT: usize = 3;
+
+/// How one particle type behaves toward another: friends weakly cohere,
+/// enemies strongly flee, neutral ignores
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum SpeciesRelation {
+    Neutral,
+    Friend,
+    Enemy,
+}
+
+/// Pull weight applied between friend-related neighbors within perception radius
+const FRIEND_PULL_WEIGHT: f32 = 0.4;
+/// Flee weight applied between enemy-related neighbors, scaled by closeness
+const ENEMY_FLEE_WEIGHT: f32 = 2.5;
+
+/// Shape of an attractor's influence region
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum AttractorKind {
+    /// Radial falloff from a center point, bounded by `extent` (a radius)
+    Sphere,
+    /// Axis-aligned square region, bounded by `extent` (a half-size)
+    Box,
+}
+
+/// A single field influence: pulls or pushes nearby particles
+#[derive(Clone, Copy)]
+struct Attractor {
+    active: bool,
+    kind: AttractorKind,
+    x: f32,
+    y: f32,
+    extent: f32,
+    strength: f32,
+    attenuation: f32,
+    directionality: f32,
+    dir_x: f32,
+    dir_y: f32,
+}
+
+impl Attractor {
+    const fn empty() -> Attractor {
+        Attractor {
+            active: false,
+            kind: AttractorKind::Sphere,
+            x: 0.0,
+            y: 0.0,
+            extent: 0.0,
+            strength: 0.0,
+            attenuation: 1.0,
+            directionality: 0.0,
+            dir_x: 0.0,
+            dir_y: 0.0,
+        }
+    }
+}
+
+/// Fixed capacity of the attractor array, kept small and branch-predictable
+const MAX_ATTRACTORS: usize = 32;
+/// Influence radius of the built-in mouse attractor, matching the old hardcoded repulsion
+const MOUSE_ATTRACTOR_EXTENT: f32 = 150.0;
+/// Push strength of the built-in mouse attractor (negative = push)
+const MOUSE_ATTRACTOR_STRENGTH: f32 = -5.0;
+
+/// Shape of a solid collider particles bounce off
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColliderKind {
+    /// Center + radius
+    Circle,
+    /// Axis-aligned square region, bounded by `extent` (a half-size)
+    Box,
+}
+
+/// A solid obstacle: particles inside its bounds get pushed out along its
+/// normal and have their velocity reflected with `restitution`
+#[derive(Clone, Copy)]
+struct Collider {
+    active: bool,
+    kind: ColliderKind,
+    x: f32,
+    y: f32,
+    extent: f32,
+    restitution: f32,
+}
+
+impl Collider {
+    const fn empty() -> Collider {
+        Collider {
+            active: false,
+            kind: ColliderKind::Circle,
+            x: 0.0,
+            y: 0.0,
+            extent: 0.0,
+            restitution: 0.5,
+        }
+    }
+}
+
+/// Fixed capacity of the collider array, kept small and branch-predictable
+const MAX_COLLIDERS: usize = 32;
+
+/// A generic 2D signed-distance field obstacle, uploaded as a flat grid with
+/// a bounding transform (`origin` + `cell_size`). Only one is active at a time.
+struct SdfField {
+    active: bool,
+    data: Vec<f32>,
+    cols: usize,
+    rows: usize,
+    origin_x: f32,
+    origin_y: f32,
+    cell_size: f32,
+    restitution: f32,
+}
+
+impl SdfField {
+    fn empty() -> SdfField {
+        SdfField {
+            active: false,
+            data: Vec::new(),
+            cols: 0,
+            rows: 0,
+            origin_x: 0.0,
+            origin_y: 0.0,
+            cell_size: 1.0,
+            restitution: 0.5,
+        }
+    }
+}
+
 /// A single particle in the swarm
 #[derive(Clone)]
 struct Particle {
@@ -26,6 +181,10 @@ struct Particle {
     target_x: f32,
     target_y: f32,
     is_forming: bool,
+    // Closest-ever approach to the assigned target, used by the PSO integrator
+    pbest_x: f32,
+    pbest_y: f32,
+    pbest_fitness: f32,
     size: f32,
     friction: f32,
     ease: f32,
@@ -40,14 +199,35 @@ pub struct SwarmSubstrate {
     particles: Vec<Particle>,
     text_coords: Vec<(f32, f32)>,
     render_buffer: Vec<f32>,  // Pre-allocated buffer for render data
+    trail_buffer: Vec<f32>,   // Ring-buffered (x, y) history per particle, empty when disabled
+    trail_heads: Vec<usize>,  // Next write slot in each particle's ring buffer
+    trail_length: usize,      // Samples per particle; 0 means trails are disabled
     width: f32,
     height: f32,
-    mouse_x: f32,
-    mouse_y: f32,
-    mouse_active: bool,
+    attractors: [Attractor; MAX_ATTRACTORS],
+    mouse_attractor_id: i32,
     time: f32,
+    flock_separation: f32,
+    flock_alignment: f32,
+    flock_cohesion: f32,
+    pso_enabled: bool,
+    pso_w: f32,
+    pso_c1: f32,
+    pso_c2: f32,
+    // Globally best-fitting particle/target pair for the current text formation
+    gbest_x: f32,
+    gbest_y: f32,
+    gbest_fitness: f32,
+    species_relations: [[SpeciesRelation; PARTICLE_TYPE_COUNT]; PARTICLE_TYPE_COUNT],
+    colliders: [Collider; MAX_COLLIDERS],
+    sdf: SdfField,
 }
 
+/// Boids perception radius (neighbor search + flock grid cell size), in pixels.
+const FLOCK_PERCEPTION_RADIUS: f32 = 50.0;
+/// Boids separation radius - neighbors closer than this push the particle away.
+const FLOCK_SEPARATION_RADIUS: f32 = 18.0;
+
 #[wasm_bindgen]
 impl SwarmSubstrate {
     /// Create a new swarm substrate
@@ -84,6 +264,9 @@ impl SwarmSubstrate {
                 target_x: x,
                 target_y: y,
                 is_forming: false,
+                pbest_x: x,
+                pbest_y: y,
+                pbest_fitness: f32::NEG_INFINITY,
                 size,
                 friction,
                 ease,
@@ -94,18 +277,246 @@ impl SwarmSubstrate {
         }
         
         let render_buffer = vec![0.0f32; particle_count * 4];
-        
-        SwarmSubstrate {
+
+        let mut substrate = SwarmSubstrate {
             particles,
             text_coords: Vec::new(),
             render_buffer,
+            trail_buffer: Vec::new(),
+            trail_heads: Vec::new(),
+            trail_length: 0,
             width,
             height,
-            mouse_x: 0.0,
-            mouse_y: 0.0,
-            mouse_active: false,
+            attractors: [Attractor::empty(); MAX_ATTRACTORS],
+            mouse_attractor_id: -1,
             time: 0.0,
+            flock_separation: 1.0,
+            flock_alignment: 1.0,
+            flock_cohesion: 1.0,
+            pso_enabled: false,
+            pso_w: 0.7,
+            pso_c1: 1.5,
+            pso_c2: 1.5,
+            gbest_x: 0.0,
+            gbest_y: 0.0,
+            gbest_fitness: f32::NEG_INFINITY,
+            species_relations: [[SpeciesRelation::Neutral; PARTICLE_TYPE_COUNT]; PARTICLE_TYPE_COUNT],
+            colliders: [Collider::empty(); MAX_COLLIDERS],
+            sdf: SdfField::empty(),
+        };
+
+        // The mouse is just a dynamically-managed sphere attractor, inactive
+        // until the first `set_mouse` call turns it on.
+        substrate.mouse_attractor_id = substrate.add_attractor(
+            AttractorKind::Sphere,
+            0.0,
+            0.0,
+            MOUSE_ATTRACTOR_EXTENT,
+            MOUSE_ATTRACTOR_STRENGTH,
+            1.0,
+            0.0,
+            0.0,
+            0.0,
+        );
+        substrate.set_mouse(0.0, 0.0, false);
+
+        substrate
+    }
+
+    /// Configure the boids flocking weights applied to floating particles
+    #[wasm_bindgen]
+    pub fn set_flock_weights(&mut self, separation: f32, alignment: f32, cohesion: f32) {
+        self.flock_separation = separation;
+        self.flock_alignment = alignment;
+        self.flock_cohesion = cohesion;
+    }
+
+    /// Toggle the PSO-style convergence integrator for forming particles;
+    /// when off they use the original eased spring toward their target
+    #[wasm_bindgen]
+    pub fn set_pso_mode(&mut self, enabled: bool) {
+        self.pso_enabled = enabled;
+    }
+
+    /// Configure PSO inertia (`w`) and cognitive/social coefficients (`c1`, `c2`)
+    #[wasm_bindgen]
+    pub fn set_pso_params(&mut self, w: f32, c1: f32, c2: f32) {
+        self.pso_w = w;
+        self.pso_c1 = c1;
+        self.pso_c2 = c2;
+    }
+
+    /// Configure how particle type `a` reacts to neighbors of type `b` during
+    /// the flocking neighbor scan: Friends add a weak cohesion pull, Enemies
+    /// add a strong flee force, Neutral has no effect
+    #[wasm_bindgen]
+    pub fn set_species_relation(&mut self, a: ParticleType, b: ParticleType, relation: SpeciesRelation) {
+        self.species_relations[a.index()][b.index()] = relation;
+    }
+
+    /// Register a new solid collider and return its id, or -1 if the
+    /// fixed-capacity collider array is full
+    #[wasm_bindgen]
+    pub fn add_collider(&mut self, kind: ColliderKind, x: f32, y: f32, extent: f32, restitution: f32) -> i32 {
+        for (i, slot) in self.colliders.iter_mut().enumerate() {
+            if !slot.active {
+                *slot = Collider {
+                    active: true,
+                    kind,
+                    x,
+                    y,
+                    extent,
+                    restitution,
+                };
+                return i as i32;
+            }
+        }
+        -1
+    }
+
+    /// Update an existing collider in place; a no-op if `id` is out of range
+    /// or was never active
+    #[wasm_bindgen]
+    pub fn update_collider(&mut self, id: i32, x: f32, y: f32, extent: f32, restitution: f32) {
+        if let Some(slot) = self.collider_slot_mut(id) {
+            slot.x = x;
+            slot.y = y;
+            slot.extent = extent;
+            slot.restitution = restitution;
+        }
+    }
+
+    /// Deactivate a collider, freeing its slot for reuse
+    #[wasm_bindgen]
+    pub fn remove_collider(&mut self, id: i32) {
+        if let Some(slot) = self.collider_slot_mut(id) {
+            *slot = Collider::empty();
+        }
+    }
+
+    /// Look up an active collider slot by id, if in range
+    fn collider_slot_mut(&mut self, id: i32) -> Option<&mut Collider> {
+        if id < 0 {
+            return None;
+        }
+        self.colliders.get_mut(id as usize).filter(|c| c.active)
+    }
+
+    /// Upload a flat signed-distance field grid as a solid obstacle, with a
+    /// bounding transform mapping world space to grid cells. Replaces any
+    /// previously uploaded SDF. A no-op if `data.len()` doesn't match `cols * rows`.
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_sdf_collider(
+        &mut self,
+        data: &[f32],
+        cols: usize,
+        rows: usize,
+        origin_x: f32,
+        origin_y: f32,
+        cell_size: f32,
+        restitution: f32,
+    ) {
+        if data.len() != cols * rows {
+            return;
+        }
+
+        self.sdf = SdfField {
+            active: true,
+            data: data.to_vec(),
+            cols,
+            rows,
+            origin_x,
+            origin_y,
+            cell_size,
+            restitution,
+        };
+    }
+
+    /// Remove the SDF obstacle and free its grid
+    #[wasm_bindgen]
+    pub fn clear_sdf_collider(&mut self) {
+        self.sdf = SdfField::empty();
+    }
+
+    /// Register a new attractor/collider field and return its id, or -1 if
+    /// the fixed-capacity attractor array is full
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_attractor(
+        &mut self,
+        kind: AttractorKind,
+        x: f32,
+        y: f32,
+        extent: f32,
+        strength: f32,
+        attenuation: f32,
+        directionality: f32,
+        dir_x: f32,
+        dir_y: f32,
+    ) -> i32 {
+        for (i, slot) in self.attractors.iter_mut().enumerate() {
+            if !slot.active {
+                *slot = Attractor {
+                    active: true,
+                    kind,
+                    x,
+                    y,
+                    extent,
+                    strength,
+                    attenuation,
+                    directionality,
+                    dir_x,
+                    dir_y,
+                };
+                return i as i32;
+            }
+        }
+        -1
+    }
+
+    /// Update an existing attractor in place; a no-op if `id` is out of
+    /// range or was never active
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_attractor(
+        &mut self,
+        id: i32,
+        x: f32,
+        y: f32,
+        extent: f32,
+        strength: f32,
+        attenuation: f32,
+        directionality: f32,
+        dir_x: f32,
+        dir_y: f32,
+    ) {
+        if let Some(slot) = self.attractor_slot_mut(id) {
+            slot.x = x;
+            slot.y = y;
+            slot.extent = extent;
+            slot.strength = strength;
+            slot.attenuation = attenuation;
+            slot.directionality = directionality;
+            slot.dir_x = dir_x;
+            slot.dir_y = dir_y;
+        }
+    }
+
+    /// Deactivate an attractor, freeing its slot for reuse
+    #[wasm_bindgen]
+    pub fn remove_attractor(&mut self, id: i32) {
+        if let Some(slot) = self.attractor_slot_mut(id) {
+            *slot = Attractor::empty();
+        }
+    }
+
+    /// Look up an active attractor slot by id, if in range
+    fn attractor_slot_mut(&mut self, id: i32) -> Option<&mut Attractor> {
+        if id < 0 {
+            return None;
         }
+        self.attractors.get_mut(id as usize).filter(|a| a.active)
     }
     
     /// Set text coordinates from JS (flattened array: [x1, y1, x2, y2, ...])
@@ -123,6 +534,9 @@ impl SwarmSubstrate {
     /// Assign particles to text coordinates using nearest-neighbor matching
     fn assign_particles_to_text(&mut self) {
         let num_coords = self.text_coords.len();
+        // Targets are about to change, so the old global best is stale
+        self.gbest_fitness = f32::NEG_INFINITY;
+
         if num_coords == 0 {
             // No text - all particles float
             for p in &mut self.particles {
@@ -130,7 +544,7 @@ impl SwarmSubstrate {
             }
             return;
         }
-        
+
         // Grid-based nearest neighbor for O(n) instead of O(n²)
         let grid_size: f32 = 50.0;
         let mut grid: std::collections::HashMap<(i32, i32), Vec<usize>> = 
@@ -183,6 +597,7 @@ impl SwarmSubstrate {
                 self.particles[i].target_x = tx;
                 self.particles[i].target_y = ty;
                 self.particles[i].is_forming = true;
+                self.particles[i].pbest_fitness = f32::NEG_INFINITY;
                 used_coords.insert(idx);
             }
         }
@@ -203,6 +618,7 @@ impl SwarmSubstrate {
                 self.particles[i].target_x = tx;
                 self.particles[i].target_y = ty;
                 self.particles[i].is_forming = true;
+                self.particles[i].pbest_fitness = f32::NEG_INFINITY;
                 used_coords.insert(unused_idx);
                 unused_idx += 1;
             } else {
@@ -214,9 +630,12 @@ impl SwarmSubstrate {
     /// Update mouse position
     #[wasm_bindgen]
     pub fn set_mouse(&mut self, x: f32, y: f32, active: bool) {
-        self.mouse_x = x;
-        self.mouse_y = y;
-        self.mouse_active = active;
+        let mouse_id = self.mouse_attractor_id;
+        if let Some(slot) = self.attractors.get_mut(mouse_id as usize) {
+            slot.active = active;
+            slot.x = x;
+            slot.y = y;
+        }
     }
     
     /// Resize the substrate
@@ -226,48 +645,368 @@ impl SwarmSubstrate {
         self.height = height;
     }
     
+    /// Build a uniform spatial hash of floating particles keyed by grid cell,
+    /// sized to the perception radius so flocking only scans 3x3 neighboring
+    /// cells per particle instead of the whole swarm.
+    fn build_flock_grid(&self) -> std::collections::HashMap<(i32, i32), Vec<usize>> {
+        let mut grid: std::collections::HashMap<(i32, i32), Vec<usize>> =
+            std::collections::HashMap::new();
+        for (idx, p) in self.particles.iter().enumerate() {
+            if !p.is_forming {
+                let key = (
+                    (p.x / FLOCK_PERCEPTION_RADIUS) as i32,
+                    (p.y / FLOCK_PERCEPTION_RADIUS) as i32,
+                );
+                grid.entry(key).or_default().push(idx);
+            }
+        }
+        grid
+    }
+
+    /// Compute boids (separation/alignment/cohesion) accelerations for every
+    /// floating particle, scanning only the 3x3 neighboring grid cells.
+    fn compute_flock_accelerations(
+        &self,
+        grid: &std::collections::HashMap<(i32, i32), Vec<usize>>,
+    ) -> Vec<(f32, f32)> {
+        let mut accel = vec![(0.0f32, 0.0f32); self.particles.len()];
+
+        for (i, p) in self.particles.iter().enumerate() {
+            if p.is_forming {
+                continue;
+            }
+
+            let gx = (p.x / FLOCK_PERCEPTION_RADIUS) as i32;
+            let gy = (p.y / FLOCK_PERCEPTION_RADIUS) as i32;
+
+            let mut sep_x = 0.0f32;
+            let mut sep_y = 0.0f32;
+            let mut align_vx = 0.0f32;
+            let mut align_vy = 0.0f32;
+            let mut align_count = 0u32;
+            let mut coh_x = 0.0f32;
+            let mut coh_y = 0.0f32;
+            let mut coh_count = 0u32;
+            let mut rel_x = 0.0f32;
+            let mut rel_y = 0.0f32;
+
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    let Some(cell) = grid.get(&(gx + dx, gy + dy)) else {
+                        continue;
+                    };
+                    for &j in cell {
+                        if j == i {
+                            continue;
+                        }
+                        let other = &self.particles[j];
+                        let ddx = other.x - p.x;
+                        let ddy = other.y - p.y;
+                        let dist = (ddx * ddx + ddy * ddy).sqrt();
+
+                        if dist < FLOCK_SEPARATION_RADIUS && dist > 0.0 {
+                            sep_x -= (ddx / dist) / dist;
+                            sep_y -= (ddy / dist) / dist;
+                        }
+
+                        if dist < FLOCK_PERCEPTION_RADIUS {
+                            align_vx += other.vx;
+                            align_vy += other.vy;
+                            align_count += 1;
+
+                            coh_x += other.x;
+                            coh_y += other.y;
+                            coh_count += 1;
+
+                            if dist > 0.0 {
+                                let relation = self.species_relations[p.particle_type.index()]
+                                    [other.particle_type.index()];
+                                match relation {
+                                    SpeciesRelation::Neutral => {}
+                                    SpeciesRelation::Friend => {
+                                        rel_x += (ddx / dist) * FRIEND_PULL_WEIGHT;
+                                        rel_y += (ddy / dist) * FRIEND_PULL_WEIGHT;
+                                    }
+                                    SpeciesRelation::Enemy => {
+                                        let closeness =
+                                            (FLOCK_PERCEPTION_RADIUS - dist) / FLOCK_PERCEPTION_RADIUS;
+                                        rel_x -= (ddx / dist) * closeness * ENEMY_FLEE_WEIGHT;
+                                        rel_y -= (ddy / dist) * closeness * ENEMY_FLEE_WEIGHT;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            let (sep_mul, align_mul, coh_mul) = p.particle_type.flock_multipliers();
+            let mut ax = sep_x * self.flock_separation * sep_mul + rel_x;
+            let mut ay = sep_y * self.flock_separation * sep_mul + rel_y;
+
+            if align_count > 0 {
+                let avg_vx = align_vx / align_count as f32;
+                let avg_vy = align_vy / align_count as f32;
+                ax += (avg_vx - p.vx) * self.flock_alignment * align_mul;
+                ay += (avg_vy - p.vy) * self.flock_alignment * align_mul;
+            }
+
+            if coh_count > 0 {
+                let centroid_x = coh_x / coh_count as f32;
+                let centroid_y = coh_y / coh_count as f32;
+                ax += (centroid_x - p.x) * self.flock_cohesion * coh_mul;
+                ay += (centroid_y - p.y) * self.flock_cohesion * coh_mul;
+            }
+
+            accel[i] = (ax, ay);
+        }
+
+        accel
+    }
+
+    /// Accumulate force from every active attractor/collider field at a point.
+    /// Positive `strength` pulls toward the field, negative pushes away; a
+    /// sphere falls off radially from its center, a box from its center within
+    /// its axis-aligned square region. `directionality` blends that radial pull
+    /// with a fixed push direction (`dir_x`, `dir_y`).
+    fn field_force_at(&self, x: f32, y: f32) -> (f32, f32) {
+        let mut force_x = 0.0f32;
+        let mut force_y = 0.0f32;
+
+        for a in self.attractors.iter() {
+            if !a.active || a.extent <= 0.0 {
+                continue;
+            }
+
+            let dx = x - a.x;
+            let dy = y - a.y;
+
+            let dist = (dx * dx + dy * dy).sqrt();
+            // Distance in the attractor's own shape: Euclidean for a sphere,
+            // Chebyshev for a box, so a box's falloff reaches zero at its square edge
+            let shape_dist = match a.kind {
+                AttractorKind::Sphere => dist,
+                AttractorKind::Box => dx.abs().max(dy.abs()),
+            };
+            if shape_dist >= a.extent {
+                continue;
+            }
+
+            let falloff = (1.0 - shape_dist / a.extent).max(0.0).powf(a.attenuation.max(0.0001));
+
+            let (nx, ny) = if dist > 0.0 { (dx / dist, dy / dist) } else { (0.0, 0.0) };
+            let radial_x = -nx * falloff * a.strength;
+            let radial_y = -ny * falloff * a.strength;
+            let fixed_x = a.dir_x * falloff * a.strength;
+            let fixed_y = a.dir_y * falloff * a.strength;
+
+            force_x += radial_x * (1.0 - a.directionality) + fixed_x * a.directionality;
+            force_y += radial_y * (1.0 - a.directionality) + fixed_y * a.directionality;
+        }
+
+        (force_x, force_y)
+    }
+
+    /// Signed distance and outward unit normal from a circle collider to a point
+    fn circle_sdf(c: &Collider, x: f32, y: f32) -> (f32, f32, f32) {
+        let dx = x - c.x;
+        let dy = y - c.y;
+        let dist = (dx * dx + dy * dy).sqrt();
+        let signed_dist = dist - c.extent;
+        if dist > 0.0001 {
+            (signed_dist, dx / dist, dy / dist)
+        } else {
+            (signed_dist, 1.0, 0.0)
+        }
+    }
+
+    /// Signed distance and outward unit normal from an axis-aligned box
+    /// collider (center `(c.x, c.y)`, half-size `c.extent`) to a point
+    fn box_sdf(c: &Collider, x: f32, y: f32) -> (f32, f32, f32) {
+        let dx = x - c.x;
+        let dy = y - c.y;
+        let qx = dx.abs() - c.extent;
+        let qy = dy.abs() - c.extent;
+
+        if qx.max(qy) < 0.0 {
+            // Inside: distance to the nearest edge, normal along that axis
+            if qx > qy {
+                (qx, dx.signum(), 0.0)
+            } else {
+                (qy, 0.0, dy.signum())
+            }
+        } else {
+            let ox = qx.max(0.0);
+            let oy = qy.max(0.0);
+            let dist = (ox * ox + oy * oy).sqrt();
+            if dist > 0.0001 {
+                (dist, (ox * dx.signum()) / dist, (oy * dy.signum()) / dist)
+            } else {
+                (dist, 0.0, 0.0)
+            }
+        }
+    }
+
+    /// Sample the SDF grid at a point, clamping lookups to the grid bounds
+    /// and treating anything outside the bounding transform as far outside
+    fn sdf_distance_at(sdf: &SdfField, x: f32, y: f32) -> f32 {
+        if !sdf.active || sdf.cols == 0 || sdf.rows == 0 {
+            return f32::INFINITY;
+        }
+
+        let fx = (x - sdf.origin_x) / sdf.cell_size;
+        let fy = (y - sdf.origin_y) / sdf.cell_size;
+        if fx < 0.0 || fy < 0.0 || fx >= sdf.cols as f32 || fy >= sdf.rows as f32 {
+            return f32::INFINITY;
+        }
+
+        let ix = (fx as usize).min(sdf.cols - 1);
+        let iy = (fy as usize).min(sdf.rows - 1);
+        sdf.data[iy * sdf.cols + ix]
+    }
+
+    /// Sample the SDF grid at a point clamped into the grid's bounds, so
+    /// points just inside the outer row/column still get a real value
+    /// instead of `sdf_distance_at`'s "far outside" sentinel
+    fn sdf_distance_clamped(sdf: &SdfField, x: f32, y: f32) -> f32 {
+        if !sdf.active || sdf.cols == 0 || sdf.rows == 0 {
+            return f32::INFINITY;
+        }
+
+        let max_x = sdf.origin_x + sdf.cols as f32 * sdf.cell_size - 0.0001;
+        let max_y = sdf.origin_y + sdf.rows as f32 * sdf.cell_size - 0.0001;
+        let cx = x.clamp(sdf.origin_x, max_x);
+        let cy = y.clamp(sdf.origin_y, max_y);
+        Self::sdf_distance_at(sdf, cx, cy)
+    }
+
+    /// Signed distance and finite-difference normal of the SDF grid at a
+    /// point, or `None` if the SDF is inactive or the point is out of bounds
+    fn sdf_gradient_at(sdf: &SdfField, x: f32, y: f32) -> Option<(f32, f32, f32)> {
+        let dist = Self::sdf_distance_at(sdf, x, y);
+        if !dist.is_finite() {
+            return None;
+        }
+
+        // Clamp the stencil samples so a point in the outer row/column of the
+        // grid doesn't pull in the "far outside" sentinel on one side
+        let h = sdf.cell_size.max(0.0001);
+        let dx = Self::sdf_distance_clamped(sdf, x + h, y) - Self::sdf_distance_clamped(sdf, x - h, y);
+        let dy = Self::sdf_distance_clamped(sdf, x, y + h) - Self::sdf_distance_clamped(sdf, x, y - h);
+        let len = (dx * dx + dy * dy).sqrt();
+
+        if len > 0.0001 {
+            Some((dist, dx / len, dy / len))
+        } else {
+            Some((dist, 0.0, 0.0))
+        }
+    }
+
+    /// Push a particle out along a collision normal and reflect its velocity
+    /// component along that normal, scaled by `restitution`
+    fn resolve_collision(p: &mut Particle, signed_dist: f32, nx: f32, ny: f32, restitution: f32) {
+        if signed_dist >= p.size {
+            return;
+        }
+
+        let penetration = p.size - signed_dist;
+        p.x += nx * penetration;
+        p.y += ny * penetration;
+
+        let normal_speed = p.vx * nx + p.vy * ny;
+        if normal_speed < 0.0 {
+            p.vx -= (1.0 + restitution) * normal_speed * nx;
+            p.vy -= (1.0 + restitution) * normal_speed * ny;
+        }
+    }
+
+    /// Resolve a particle against every active collider and the SDF obstacle
+    fn apply_colliders(colliders: &[Collider; MAX_COLLIDERS], sdf: &SdfField, p: &mut Particle) {
+        for c in colliders.iter() {
+            if !c.active {
+                continue;
+            }
+            let (signed_dist, nx, ny) = match c.kind {
+                ColliderKind::Circle => Self::circle_sdf(c, p.x, p.y),
+                ColliderKind::Box => Self::box_sdf(c, p.x, p.y),
+            };
+            Self::resolve_collision(p, signed_dist, nx, ny, c.restitution);
+        }
+
+        if let Some((signed_dist, nx, ny)) = Self::sdf_gradient_at(sdf, p.x, p.y) {
+            Self::resolve_collision(p, signed_dist, nx, ny, sdf.restitution);
+        }
+    }
+
     /// Step the simulation forward one frame
     #[wasm_bindgen]
     pub fn step(&mut self) {
         self.time += 0.02;
-        let mouse_radius: f32 = 150.0;
-        
-        for p in &mut self.particles {
-            // Mouse repulsion
-            let mut force_x: f32 = 0.0;
-            let mut force_y: f32 = 0.0;
-            
-            if self.mouse_active {
-                let mdx = self.mouse_x - p.x;
-                let mdy = self.mouse_y - p.y;
-                let dist = (mdx * mdx + mdy * mdy).sqrt();
-                
-                if dist < mouse_radius && dist > 0.0 {
-                    let force = (mouse_radius - dist) / mouse_radius;
-                    let angle = mdy.atan2(mdx);
-                    force_x = -angle.cos() * force * 5.0;
-                    force_y = -angle.sin() * force * 5.0;
-                }
-            }
-            
+
+        let flock_grid = self.build_flock_grid();
+        let flock_accel = self.compute_flock_accelerations(&flock_grid);
+        let field_forces: Vec<(f32, f32)> = self
+            .particles
+            .iter()
+            .map(|p| self.field_force_at(p.x, p.y))
+            .collect();
+
+        let mut rng = rand::thread_rng();
+        let mut gbest_x = self.gbest_x;
+        let mut gbest_y = self.gbest_y;
+        let mut gbest_fitness = self.gbest_fitness;
+
+        for (i, p) in self.particles.iter_mut().enumerate() {
+            let (force_x, force_y) = field_forces[i];
+
             // Physics update
             if p.is_forming {
-                // Breathing effect
-                let breathing = (self.time + p.y * 0.05).sin() * 2.0;
-                let target_x = p.target_x + breathing;
-                let target_y = p.target_y + breathing;
-                
-                let ddx = target_x - p.x;
-                let ddy = target_y - p.y;
-                
-                // Move towards target
-                p.vx += (ddx * p.ease * p.attraction_strength + force_x) * 0.1;
-                p.vy += (ddy * p.ease * p.attraction_strength + force_y) * 0.1;
+                if self.pso_enabled {
+                    // Fitness is negative squared distance to the assigned target
+                    let ddx = p.target_x - p.x;
+                    let ddy = p.target_y - p.y;
+                    let fitness = -(ddx * ddx + ddy * ddy);
+
+                    if fitness > p.pbest_fitness {
+                        p.pbest_fitness = fitness;
+                        p.pbest_x = p.x;
+                        p.pbest_y = p.y;
+                    }
+                    if fitness > gbest_fitness {
+                        gbest_fitness = fitness;
+                        gbest_x = p.x;
+                        gbest_y = p.y;
+                    }
+
+                    let r1: f32 = rng.gen();
+                    let r2: f32 = rng.gen();
+                    p.vx = self.pso_w * p.vx
+                        + self.pso_c1 * r1 * (p.pbest_x - p.x)
+                        + self.pso_c2 * r2 * (gbest_x - p.x)
+                        + force_x * 0.1;
+                    p.vy = self.pso_w * p.vy
+                        + self.pso_c1 * r1 * (p.pbest_y - p.y)
+                        + self.pso_c2 * r2 * (gbest_y - p.y)
+                        + force_y * 0.1;
+                } else {
+                    // Breathing effect
+                    let breathing = (self.time + p.y * 0.05).sin() * 2.0;
+                    let target_x = p.target_x + breathing;
+                    let target_y = p.target_y + breathing;
+
+                    let ddx = target_x - p.x;
+                    let ddy = target_y - p.y;
+
+                    // Move towards target
+                    p.vx += (ddx * p.ease * p.attraction_strength + force_x) * 0.1;
+                    p.vy += (ddy * p.ease * p.attraction_strength + force_y) * 0.1;
+                }
             } else {
-                // Float randomly
-                p.vx += force_x * 0.5;
-                p.vy += force_y * 0.5;
-                
+                // Float randomly, steered by boids separation/alignment/cohesion
+                let (flock_ax, flock_ay) = flock_accel[i];
+                p.vx += force_x * 0.5 + flock_ax * 0.1;
+                p.vy += force_y * 0.5 + flock_ay * 0.1;
+
                 // Boundary bounce
                 if p.x < 0.0 || p.x > self.width {
                     p.vx *= -1.0;
@@ -291,9 +1030,25 @@ impl SwarmSubstrate {
             // Update position
             p.x += p.vx;
             p.y += p.vy;
+
+            // Flow around solid obstacles instead of passing through them
+            Self::apply_colliders(&self.colliders, &self.sdf, p);
+
+            // Record trail history (fixed-stride ring buffer write, wrapping head)
+            if self.trail_length > 0 {
+                let head = self.trail_heads[i];
+                let idx = (i * self.trail_length + head) * 2;
+                self.trail_buffer[idx] = p.x;
+                self.trail_buffer[idx + 1] = p.y;
+                self.trail_heads[i] = (head + 1) % self.trail_length;
+            }
         }
+
+        self.gbest_x = gbest_x;
+        self.gbest_y = gbest_y;
+        self.gbest_fitness = gbest_fitness;
     }
-    
+
     /// Get particle count
     #[wasm_bindgen]
     pub fn particle_count(&self) -> usize {
@@ -325,7 +1080,73 @@ impl SwarmSubstrate {
     pub fn render_buffer_len(&self) -> usize {
         self.render_buffer.len()
     }
-    
+
+    /// Enable per-particle trail history of the last `length` (x, y) samples,
+    /// for motion-blur rendering. Pass 0 to disable and free the buffer.
+    #[wasm_bindgen]
+    pub fn enable_trails(&mut self, length: usize) {
+        self.trail_length = length;
+        if length == 0 {
+            self.trail_buffer = Vec::new();
+            self.trail_heads = Vec::new();
+            return;
+        }
+
+        self.trail_buffer = vec![0.0f32; self.particles.len() * length * 2];
+        self.trail_heads = vec![0usize; self.particles.len()];
+        for (i, p) in self.particles.iter().enumerate() {
+            for slot in 0..length {
+                let idx = (i * length + slot) * 2;
+                self.trail_buffer[idx] = p.x;
+                self.trail_buffer[idx + 1] = p.y;
+            }
+        }
+    }
+
+    /// Get pointer to the trail ring buffer for direct memory access, or
+    /// null when trails are disabled
+    #[wasm_bindgen]
+    pub fn trail_buffer_ptr(&self) -> *const f32 {
+        if self.trail_length == 0 {
+            std::ptr::null()
+        } else {
+            self.trail_buffer.as_ptr()
+        }
+    }
+
+    /// Get trail buffer length (0 when trails are disabled)
+    #[wasm_bindgen]
+    pub fn trail_buffer_len(&self) -> usize {
+        self.trail_buffer.len()
+    }
+
+    /// Get the configured trail length in samples (0 when disabled)
+    #[wasm_bindgen]
+    pub fn trail_length(&self) -> usize {
+        self.trail_length
+    }
+
+    /// Get pointer to the per-particle trail head indices, or null when
+    /// trails are disabled. `trail_heads[i]` is the slot the *next* write for
+    /// particle `i` will land on, so the most recently written sample is at
+    /// slot `(trail_heads[i] + trail_length - 1) % trail_length` - JS needs
+    /// this to walk each particle's ring buffer in time order instead of
+    /// raw storage order.
+    #[wasm_bindgen]
+    pub fn trail_head_ptr(&self) -> *const usize {
+        if self.trail_length == 0 {
+            std::ptr::null()
+        } else {
+            self.trail_heads.as_ptr()
+        }
+    }
+
+    /// Get trail head array length (0 when trails are disabled)
+    #[wasm_bindgen]
+    pub fn trail_head_len(&self) -> usize {
+        self.trail_heads.len()
+    }
+
     /// Get stats for debugging
     #[wasm_bindgen]
     pub fn get_stats(&self) -> String {